@@ -0,0 +1,50 @@
+//! Backs the `attr = value,` and `type = value,` arms of `html!`, letting an
+//! attribute be conditionally present (`Option<T>`) or a bare on/off toggle
+//! (`bool`) instead of always being stringified into the DOM.
+
+/// Converts a value given to an attribute position in `html!` into the
+/// attribute's on/off state: `Some(text)` sets it to `text`, `None` omits (or
+/// removes, on re-render) it entirely.
+pub trait IntoAttribute {
+    fn into_attribute(self) -> Option<String>;
+}
+
+/// A presence attribute like `disabled`/`checked`: present with no value
+/// when `true`, absent when `false`.
+impl IntoAttribute for bool {
+    fn into_attribute(self) -> Option<String> {
+        if self {
+            Some(String::new())
+        } else {
+            None
+        }
+    }
+}
+
+/// A conditionally-present attribute: `None` omits it, `Some(value)` defers
+/// to `value`'s own conversion.
+impl<T: IntoAttribute> IntoAttribute for Option<T> {
+    fn into_attribute(self) -> Option<String> {
+        self.and_then(IntoAttribute::into_attribute)
+    }
+}
+
+macro_rules! impl_into_attribute_tostring {
+    ($($ty:ty),* $(,)*) => {
+        $(
+            impl IntoAttribute for $ty {
+                fn into_attribute(self) -> Option<String> {
+                    Some(self.to_string())
+                }
+            }
+        )*
+    };
+}
+
+impl_into_attribute_tostring!(
+    String, &'static str,
+    i8, i16, i32, i64, isize,
+    u8, u16, u32, u64, usize,
+    f32, f64,
+    char,
+);
@@ -0,0 +1,58 @@
+use std::any::Any;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+/// Implemented by the root type of a child component so it can be instantiated
+/// from `html!` with the `<Name: prop=value, />` tag form.
+pub trait Component {}
+
+/// A child component node produced by the `<Name: prop=value, />` form.
+///
+/// Properties are kept type-erased here, one `Box<Any>` per named prop, rather
+/// than stringified into HTML attributes like a plain element's would be. The
+/// component's own `create`/`view` step is expected to pull them back out by
+/// name via [`property`](VComp::property).
+pub struct VComp<MSG> {
+    type_name: &'static str,
+    key: Option<String>,
+    props: HashMap<&'static str, Box<Any>>,
+    _marker: PhantomData<MSG>,
+}
+
+impl<MSG> VComp<MSG> {
+    #[doc(hidden)]
+    pub fn new<COMP: Component>(type_name: &'static str) -> Self {
+        VComp {
+            type_name,
+            key: None,
+            props: HashMap::new(),
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    #[doc(hidden)]
+    pub fn set_key<T: ToString>(&mut self, key: T) {
+        self.key = Some(key.to_string());
+    }
+
+    /// The `key` set on this component via `key="...",`, used by
+    /// [`diff_children`] to match it against its counterpart from the
+    /// previous render.
+    pub fn key(&self) -> Option<&str> {
+        self.key.as_deref()
+    }
+
+    #[doc(hidden)]
+    pub fn set_property<T: 'static>(&mut self, name: &'static str, value: T) {
+        self.props.insert(name, Box::new(value));
+    }
+
+    /// Retrieves a property set from `html!` by name, downcasting it back to `T`.
+    pub fn property<T: 'static>(&self, name: &str) -> Option<&T> {
+        self.props.get(name).and_then(|value| value.downcast_ref())
+    }
+}
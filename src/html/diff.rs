@@ -0,0 +1,153 @@
+//! Keyed reconciliation for a parent's children, as produced by the `{ for
+//! expr }` arm of `html!` once each item carries a `key`.
+
+use std::collections::HashMap;
+
+use html::Child;
+
+/// What to do with one slot of a parent's new children, decided by
+/// [`diff_children`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ChildPatch {
+    /// Reuse and (if needed) move the old child at `old_index` to represent
+    /// the new child at `new_index`, instead of recreating it.
+    Reuse { old_index: usize, new_index: usize },
+    /// No old child matched: mount the new child at `new_index` from scratch.
+    Create { new_index: usize },
+    /// No new child claimed the old child at `old_index`: unmount it.
+    Remove { old_index: usize },
+}
+
+/// Diffs a parent's previous children against its next ones.
+///
+/// Children are matched by `key` first, falling back to today's positional
+/// matching for children without one (in first-unmatched-slot order), so
+/// unkeyed lists keep their existing behavior exactly.
+///
+/// Keys are assumed unique within `new` (as they should be - that's the
+/// whole point of giving a list stable identity). If `new` does contain
+/// duplicates, only the first occurrence reuses the matching `old` child;
+/// later ones with the same key are treated as freshly created, the same as
+/// an unmatched key.
+pub fn diff_children<MSG>(old: &[Child<MSG>], new: &[Child<MSG>]) -> Vec<ChildPatch> {
+    let mut by_key = HashMap::new();
+    for (old_index, child) in old.iter().enumerate() {
+        if let Some(key) = child.key() {
+            by_key.insert(key, old_index);
+        }
+    }
+
+    let mut matched_old = vec![false; old.len()];
+    let mut next_unkeyed_old = 0;
+    let mut patches = Vec::with_capacity(new.len());
+
+    for (new_index, child) in new.iter().enumerate() {
+        let matched_index = match child.key() {
+            Some(key) => by_key.get(key).cloned().filter(|&old_index| !matched_old[old_index]),
+            None => (next_unkeyed_old..old.len())
+                .find(|&old_index| !matched_old[old_index] && old[old_index].key().is_none()),
+        };
+
+        match matched_index {
+            Some(old_index) => {
+                matched_old[old_index] = true;
+                if child.key().is_none() {
+                    next_unkeyed_old = old_index + 1;
+                }
+                patches.push(ChildPatch::Reuse { old_index, new_index });
+            }
+            None => patches.push(ChildPatch::Create { new_index }),
+        }
+    }
+
+    for (old_index, matched) in matched_old.into_iter().enumerate() {
+        if !matched {
+            patches.push(ChildPatch::Remove { old_index });
+        }
+    }
+
+    patches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use html::VNode;
+
+    fn keyed(key: &str) -> Child<()> {
+        let mut node = VNode::<()>::new("li");
+        node.set_key(key);
+        Child::VNode(node)
+    }
+
+    fn unkeyed() -> Child<()> {
+        Child::Text("item".to_string())
+    }
+
+    #[test]
+    fn reorders_keyed_children() {
+        let old = [keyed("a"), keyed("b"), keyed("c")];
+        let new = [keyed("c"), keyed("a"), keyed("b")];
+
+        let patches = diff_children(&old, &new);
+
+        assert_eq!(
+            patches,
+            vec![
+                ChildPatch::Reuse { old_index: 2, new_index: 0 },
+                ChildPatch::Reuse { old_index: 0, new_index: 1 },
+                ChildPatch::Reuse { old_index: 1, new_index: 2 },
+            ]
+        );
+    }
+
+    #[test]
+    fn removes_and_inserts_keyed_children() {
+        let old = [keyed("a"), keyed("b")];
+        let new = [keyed("b"), keyed("c")];
+
+        let patches = diff_children(&old, &new);
+
+        assert_eq!(
+            patches,
+            vec![
+                ChildPatch::Reuse { old_index: 1, new_index: 0 },
+                ChildPatch::Create { new_index: 1 },
+                ChildPatch::Remove { old_index: 0 },
+            ]
+        );
+    }
+
+    #[test]
+    fn only_first_of_a_duplicate_key_reuses_the_old_child() {
+        let old = [keyed("a")];
+        let new = [keyed("a"), keyed("a")];
+
+        let patches = diff_children(&old, &new);
+
+        assert_eq!(
+            patches,
+            vec![
+                ChildPatch::Reuse { old_index: 0, new_index: 0 },
+                ChildPatch::Create { new_index: 1 },
+            ]
+        );
+    }
+
+    #[test]
+    fn matches_unkeyed_children_positionally_around_keyed_ones() {
+        let old = [unkeyed(), keyed("a"), unkeyed()];
+        let new = [unkeyed(), unkeyed(), keyed("a")];
+
+        let patches = diff_children(&old, &new);
+
+        assert_eq!(
+            patches,
+            vec![
+                ChildPatch::Reuse { old_index: 0, new_index: 0 },
+                ChildPatch::Reuse { old_index: 2, new_index: 1 },
+                ChildPatch::Reuse { old_index: 1, new_index: 2 },
+            ]
+        );
+    }
+}
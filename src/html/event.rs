@@ -0,0 +1,67 @@
+//! Typed payloads handed to `html!` event closures, e.g. `onclick=|e: MouseData| ...`.
+
+/// Carried by mouse events: clicks, movement, hover and the context menu.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MouseData {
+    pub client_x: i32,
+    pub client_y: i32,
+    pub screen_x: i32,
+    pub screen_y: i32,
+    pub alt_key: bool,
+    pub ctrl_key: bool,
+    pub meta_key: bool,
+    pub shift_key: bool,
+}
+
+/// Carried by keyboard events.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct KeyData {
+    pub key: String,
+    pub alt_key: bool,
+    pub ctrl_key: bool,
+    pub meta_key: bool,
+    pub shift_key: bool,
+}
+
+/// Carried by `onfocus`/`onblur`. Empty for now; reserved for `related_target`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FocusData;
+
+/// Carried by `oninput`/`onchange`: the element's value after the edit.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct InputData {
+    pub value: String,
+}
+
+/// Carried by `onsubmit`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FormData;
+
+/// Carried by `onwheel`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct WheelData {
+    pub delta_x: f64,
+    pub delta_y: f64,
+}
+
+/// Carried by the `ontouch*` family.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TouchData {
+    pub client_x: i32,
+    pub client_y: i32,
+}
+
+/// Carried by the `onpointer*` family.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PointerData {
+    pub client_x: i32,
+    pub client_y: i32,
+    pub pointer_id: i32,
+}
+
+/// Carried by `onscroll`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ScrollData {
+    pub scroll_top: f64,
+    pub scroll_left: f64,
+}
@@ -0,0 +1,90 @@
+//! Backs the `$crate::html::$action::Wrapper` path used by the `(action)=handler,`
+//! arm of `html_impl!`. One module per event name, generated by `impl_listener!`
+//! so adding a new DOM event is a single macro invocation below.
+
+use html::event::{
+    FocusData, FormData, InputData, KeyData, MouseData, PointerData, ScrollData, TouchData,
+    WheelData,
+};
+
+// Generates `pub mod $module { pub struct Wrapper<MSG, F> { ... } }`, wiring the
+// closure up to fire with `$data` and identifying itself to the (future) DOM
+// binding layer as `$kind`.
+macro_rules! impl_listener {
+    ($module:ident, $kind:expr, $data:ty) => {
+        pub mod $module {
+            use std::marker::PhantomData;
+            use html::Listener;
+            use super::*;
+
+            pub struct Wrapper<MSG, F> {
+                callback: F,
+                _msg: PhantomData<MSG>,
+            }
+
+            impl<MSG, F> From<F> for Wrapper<MSG, F>
+            where
+                F: Fn($data) -> MSG,
+            {
+                fn from(callback: F) -> Self {
+                    Wrapper {
+                        callback,
+                        _msg: PhantomData,
+                    }
+                }
+            }
+
+            impl<MSG, F> Wrapper<MSG, F>
+            where
+                F: Fn($data) -> MSG,
+            {
+                pub fn emit(&self, data: $data) -> MSG {
+                    (self.callback)(data)
+                }
+            }
+
+            impl<MSG, F> Listener<MSG> for Wrapper<MSG, F>
+            where
+                F: Fn($data) -> MSG,
+            {
+                fn kind(&self) -> &'static str {
+                    $kind
+                }
+            }
+        }
+    };
+}
+
+// Mouse
+impl_listener!(onclick, "click", MouseData);
+impl_listener!(ondoubleclick, "dblclick", MouseData);
+impl_listener!(onmousedown, "mousedown", MouseData);
+impl_listener!(onmouseup, "mouseup", MouseData);
+impl_listener!(onmousemove, "mousemove", MouseData);
+impl_listener!(onmouseover, "mouseover", MouseData);
+impl_listener!(onmouseout, "mouseout", MouseData);
+impl_listener!(oncontextmenu, "contextmenu", MouseData);
+
+// Keyboard
+impl_listener!(onkeypress, "keypress", KeyData);
+impl_listener!(onkeydown, "keydown", KeyData);
+impl_listener!(onkeyup, "keyup", KeyData);
+
+// Focus
+impl_listener!(onfocus, "focus", FocusData);
+impl_listener!(onblur, "blur", FocusData);
+
+// Form
+impl_listener!(oninput, "input", InputData);
+impl_listener!(onchange, "change", InputData);
+impl_listener!(onsubmit, "submit", FormData);
+
+// Pointer / touch / wheel / scroll
+impl_listener!(onpointerdown, "pointerdown", PointerData);
+impl_listener!(onpointerup, "pointerup", PointerData);
+impl_listener!(onpointermove, "pointermove", PointerData);
+impl_listener!(ontouchstart, "touchstart", TouchData);
+impl_listener!(ontouchmove, "touchmove", TouchData);
+impl_listener!(ontouchend, "touchend", TouchData);
+impl_listener!(onwheel, "wheel", WheelData);
+impl_listener!(onscroll, "scroll", ScrollData);
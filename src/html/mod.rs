@@ -0,0 +1,214 @@
+//! The virtual DOM types produced by the `html!` macro.
+
+mod attribute;
+mod component;
+mod diff;
+mod event;
+mod listener;
+
+pub use self::attribute::IntoAttribute;
+pub use self::component::{Component, VComp};
+pub use self::diff::{diff_children, ChildPatch};
+pub use self::event::*;
+pub use self::listener::*;
+
+/// A virtual node built by `html!`: either a single DOM element, or a fragment
+/// (see [`VNode::List`]) that mounts its children inline with no wrapper
+/// element of its own.
+pub enum VNode<MSG> {
+    Element(VElement<MSG>),
+    /// A fragment: `html!`'s output when it has more than one root, the
+    /// result of an explicit `<></>`, or the single-item wrapper around a
+    /// component at the document root. Mounts/reconciles its children in place,
+    /// one after another, without an element of its own in the DOM.
+    List(Vec<Child<MSG>>),
+}
+
+impl<MSG> VNode<MSG> {
+    pub fn new(tag: &'static str) -> Self {
+        VNode::Element(VElement::new(tag))
+    }
+
+    /// An empty fragment, as produced by the explicit `<></>` syntax.
+    pub fn empty_list() -> Self {
+        VNode::List(Vec::new())
+    }
+
+    pub fn tag(&self) -> &'static str {
+        match *self {
+            VNode::Element(ref elem) => elem.tag,
+            VNode::List(_) => "",
+        }
+    }
+
+    pub fn set_value<T: ToString>(&mut self, value: &T) {
+        self.as_element_mut("set_value").set_value(value);
+    }
+
+    pub fn add_attribute(&mut self, name: &'static str, value: Option<String>) {
+        self.as_element_mut("add_attribute").add_attribute(name, value);
+    }
+
+    pub fn add_classes(&mut self, class: &'static str) {
+        self.as_element_mut("add_classes").add_classes(class);
+    }
+
+    pub fn add_listener(&mut self, listener: Box<Listener<MSG>>) {
+        self.as_element_mut("add_listener").add_listener(listener);
+    }
+
+    pub fn add_child(&mut self, child: Child<MSG>) {
+        match *self {
+            VNode::Element(ref mut elem) => elem.add_child(child),
+            VNode::List(ref mut children) => children.push(child),
+        }
+    }
+
+    pub fn set_key<T: ToString>(&mut self, key: T) {
+        self.as_element_mut("set_key").set_key(key);
+    }
+
+    /// The `key` attribute set on this node, used by [`diff_children`] to
+    /// match it against its counterpart from the previous render.
+    pub fn key(&self) -> Option<&str> {
+        match *self {
+            VNode::Element(ref elem) => elem.key(),
+            VNode::List(_) => None,
+        }
+    }
+
+    // `html_impl!` only ever calls the element-only methods above on a node it
+    // just pushed via `VNode::new`, so this should never actually panic; kept
+    // as a defensive invariant rather than silently doing nothing.
+    fn as_element_mut(&mut self, what: &'static str) -> &mut VElement<MSG> {
+        match *self {
+            VNode::Element(ref mut elem) => elem,
+            VNode::List(_) => panic!("cannot {} on a fragment (VNode::List)", what),
+        }
+    }
+}
+
+/// The element half of `VNode`: a tag name plus its attributes, classes,
+/// listeners and children.
+pub struct VElement<MSG> {
+    tag: &'static str,
+    key: Option<String>,
+    value: Option<String>,
+    classes: Vec<&'static str>,
+    attributes: Vec<(&'static str, String)>,
+    listeners: Vec<Box<Listener<MSG>>>,
+    children: Vec<Child<MSG>>,
+}
+
+impl<MSG> VElement<MSG> {
+    pub fn new(tag: &'static str) -> Self {
+        VElement {
+            tag,
+            key: None,
+            value: None,
+            classes: Vec::new(),
+            attributes: Vec::new(),
+            listeners: Vec::new(),
+            children: Vec::new(),
+        }
+    }
+
+    pub fn set_key<T: ToString>(&mut self, key: T) {
+        self.key = Some(key.to_string());
+    }
+
+    pub fn key(&self) -> Option<&str> {
+        self.key.as_deref()
+    }
+
+    pub fn set_value<T: ToString>(&mut self, value: &T) {
+        self.value = Some(value.to_string());
+    }
+
+    /// Sets `name` to `value`, or - when `value` is `None` - removes it so a
+    /// previously-present attribute can be dropped from the DOM on re-render.
+    pub fn add_attribute(&mut self, name: &'static str, value: Option<String>) {
+        self.attributes.retain(|&(existing, _)| existing != name);
+        if let Some(value) = value {
+            self.attributes.push((name, value));
+        }
+    }
+
+    pub fn add_classes(&mut self, class: &'static str) {
+        self.classes.push(class);
+    }
+
+    pub fn add_listener(&mut self, listener: Box<Listener<MSG>>) {
+        self.listeners.push(listener);
+    }
+
+    pub fn add_child(&mut self, child: Child<MSG>) {
+        self.children.push(child);
+    }
+}
+
+/// A listener attached to a `VNode` by one of the `on*` macro arms.
+pub trait Listener<MSG> {
+    /// The DOM event name this listener should be bound to, e.g. `"click"`.
+    fn kind(&self) -> &'static str;
+}
+
+impl<MSG> ::std::fmt::Debug for Listener<MSG> {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        write!(f, "Listener({})", self.kind())
+    }
+}
+
+/// A single child of a `VNode`, as produced by the `{ expr }` and `{ for expr }`
+/// arms of `html!`, or by nesting one tag inside another.
+pub enum Child<MSG> {
+    VNode(VNode<MSG>),
+    VComp(VComp<MSG>),
+    Text(String),
+}
+
+impl<MSG> Child<MSG> {
+    /// The `key` of the underlying node, if it has one. Elements and
+    /// components can both carry one; text is always matched positionally.
+    pub fn key(&self) -> Option<&str> {
+        match *self {
+            Child::VNode(ref node) => node.key(),
+            Child::VComp(ref comp) => comp.key(),
+            Child::Text(_) => None,
+        }
+    }
+}
+
+impl<MSG> ::std::fmt::Debug for Child<MSG> {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        match *self {
+            Child::VNode(ref node) => write!(f, "Child::VNode(<{}>)", node.tag()),
+            Child::VComp(ref comp) => write!(f, "Child::VComp(<{}>)", comp.type_name()),
+            Child::Text(ref text) => write!(f, "Child::Text({:?})", text),
+        }
+    }
+}
+
+impl<MSG> From<VNode<MSG>> for Child<MSG> {
+    fn from(node: VNode<MSG>) -> Self {
+        Child::VNode(node)
+    }
+}
+
+impl<MSG> From<VComp<MSG>> for Child<MSG> {
+    fn from(comp: VComp<MSG>) -> Self {
+        Child::VComp(comp)
+    }
+}
+
+impl<MSG> From<String> for Child<MSG> {
+    fn from(text: String) -> Self {
+        Child::Text(text)
+    }
+}
+
+impl<'a, MSG> From<&'a str> for Child<MSG> {
+    fn from(text: &'a str) -> Self {
+        Child::Text(text.to_string())
+    }
+}
@@ -1,85 +1,238 @@
-use html::{VNode, Child, Listener};
+use html::{VNode, Child, VComp, Listener, IntoAttribute};
 
+// `html_impl!` threads two things through every recursive call alongside the
+// token tail: `$stack`, the runtime `Vec<VNode<MSG>>` being built, and
+// `[$($tags:tt)*]`, a *compile-time* list of the currently-open tag names
+// (most recently opened first). The list doubles as the expansion-depth
+// counter below and lets a few classes of malformed markup be rejected with
+// `compile_error!` at the call site instead of panicking once the generated
+// code actually runs.
+//
+// Comparing two arbitrary identifiers for equality isn't something
+// `macro_rules!` can do on its own (that needs a proc-macro), so a genuinely
+// *wrong* closing tag (`<div></span>`) is still caught at runtime by
+// `child_to_parent`. Everything that only depends on whether there is an
+// open tag at all - a redundant `</..>` or a stray attribute - is provable
+// from `$tags` alone and is checked here instead; an empty `html!{}` is
+// rejected by `html!` itself.
 #[macro_export]
 macro_rules! html_impl {
-    // Start of openging tag
-    ($stack:ident (< $starttag:ident $($tail:tt)*)) => {
+    // Start of a child component tag: `<Name: prop=value, .../>`. Tried
+    // before the depth-guard arm below: component tags are self-closing and
+    // never push onto `$tags`, so one at the 16-deep boundary adds no
+    // nesting of its own and shouldn't be rejected by it.
+    ($stack:ident [$($tags:tt)*] (< $comptag:ident : $($tail:tt)*)) => {
+        let mut comp = $crate::html::VComp::new::<$comptag>(stringify!($comptag));
+        html_comp_impl! { $stack [$($tags)*] $comptag comp ($($tail)*) }
+    };
+    // Markup nested deeper than this is rejected below instead of silently
+    // blowing the compiler's default macro expansion limit.
+    ($stack:ident [$t1:tt $t2:tt $t3:tt $t4:tt $t5:tt $t6:tt $t7:tt $t8:tt
+                    $t9:tt $t10:tt $t11:tt $t12:tt $t13:tt $t14:tt $t15:tt $t16:tt]
+                   (< $starttag:ident $($tail:tt)*)) => {
+        compile_error!("markup too deeply nested: html! supports at most 16 levels of nested elements");
+    };
+    // Start of opening tag
+    ($stack:ident [$($tags:tt)*] (< $starttag:ident $($tail:tt)*)) => {
         let node = $crate::html::VNode::new(stringify!($starttag));
         $stack.push(node);
-        html_impl! { $stack ($($tail)*) }
+        html_impl! { $stack [$starttag $($tags)*] ($($tail)*) }
+    };
+    // PATTERN: class="", - no open tag to attach it to
+    ($stack:ident [] (class = $class:expr, $($tail:tt)*)) => {
+        compile_error!("`class` attribute with no open tag");
     };
     // PATTERN: class="",
-    ($stack:ident (class = $class:expr, $($tail:tt)*)) => {
+    ($stack:ident [$($tags:tt)+] (class = $class:expr, $($tail:tt)*)) => {
         $crate::macros::attach_class(&mut $stack, $class);
-        html_impl! { $stack ($($tail)*) }
+        html_impl! { $stack [$($tags)+] ($($tail)*) }
+    };
+    // PATTERN: value="", - no open tag to attach it to
+    ($stack:ident [] (value = $value:expr, $($tail:tt)*)) => {
+        compile_error!("`value` attribute with no open tag");
     };
     // PATTERN: value="",
-    ($stack:ident (value = $value:expr, $($tail:tt)*)) => {
+    ($stack:ident [$($tags:tt)+] (value = $value:expr, $($tail:tt)*)) => {
         $crate::macros::set_value(&mut $stack, $value);
-        html_impl! { $stack ($($tail)*) }
+        html_impl! { $stack [$($tags)+] ($($tail)*) }
+    };
+    // PATTERN: key="", - no open tag to attach it to
+    ($stack:ident [] (key = $key:expr, $($tail:tt)*)) => {
+        compile_error!("`key` attribute with no open tag");
+    };
+    // PATTERN: key="", - identity used to match this node across renders
+    ($stack:ident [$($tags:tt)+] (key = $key:expr, $($tail:tt)*)) => {
+        $crate::macros::set_key(&mut $stack, $key);
+        html_impl! { $stack [$($tags)+] ($($tail)*) }
+    };
+    // Events: mouse
+    ($stack:ident [$($tags:tt)*] (onclick = $handler:expr, $($tail:tt)*)) => {
+        html_impl! { $stack [$($tags)*] ((onclick) = $handler, $($tail)*) }
+    };
+    ($stack:ident [$($tags:tt)*] (ondoubleclick = $handler:expr, $($tail:tt)*)) => {
+        html_impl! { $stack [$($tags)*] ((ondoubleclick) = $handler, $($tail)*) }
+    };
+    ($stack:ident [$($tags:tt)*] (onmousedown = $handler:expr, $($tail:tt)*)) => {
+        html_impl! { $stack [$($tags)*] ((onmousedown) = $handler, $($tail)*) }
+    };
+    ($stack:ident [$($tags:tt)*] (onmouseup = $handler:expr, $($tail:tt)*)) => {
+        html_impl! { $stack [$($tags)*] ((onmouseup) = $handler, $($tail)*) }
+    };
+    ($stack:ident [$($tags:tt)*] (onmousemove = $handler:expr, $($tail:tt)*)) => {
+        html_impl! { $stack [$($tags)*] ((onmousemove) = $handler, $($tail)*) }
+    };
+    ($stack:ident [$($tags:tt)*] (onmouseover = $handler:expr, $($tail:tt)*)) => {
+        html_impl! { $stack [$($tags)*] ((onmouseover) = $handler, $($tail)*) }
+    };
+    ($stack:ident [$($tags:tt)*] (onmouseout = $handler:expr, $($tail:tt)*)) => {
+        html_impl! { $stack [$($tags)*] ((onmouseout) = $handler, $($tail)*) }
+    };
+    ($stack:ident [$($tags:tt)*] (oncontextmenu = $handler:expr, $($tail:tt)*)) => {
+        html_impl! { $stack [$($tags)*] ((oncontextmenu) = $handler, $($tail)*) }
+    };
+    // Events: keyboard
+    ($stack:ident [$($tags:tt)*] (onkeypress = $handler:expr, $($tail:tt)*)) => {
+        html_impl! { $stack [$($tags)*] ((onkeypress) = $handler, $($tail)*) }
+    };
+    ($stack:ident [$($tags:tt)*] (onkeydown = $handler:expr, $($tail:tt)*)) => {
+        html_impl! { $stack [$($tags)*] ((onkeydown) = $handler, $($tail)*) }
+    };
+    ($stack:ident [$($tags:tt)*] (onkeyup = $handler:expr, $($tail:tt)*)) => {
+        html_impl! { $stack [$($tags)*] ((onkeyup) = $handler, $($tail)*) }
+    };
+    // Events: focus
+    ($stack:ident [$($tags:tt)*] (onfocus = $handler:expr, $($tail:tt)*)) => {
+        html_impl! { $stack [$($tags)*] ((onfocus) = $handler, $($tail)*) }
+    };
+    ($stack:ident [$($tags:tt)*] (onblur = $handler:expr, $($tail:tt)*)) => {
+        html_impl! { $stack [$($tags)*] ((onblur) = $handler, $($tail)*) }
     };
-    // Events:
-    ($stack:ident (onclick = $handler:expr, $($tail:tt)*)) => {
-        html_impl! { $stack ((onclick) = $handler, $($tail)*) }
+    // Events: form
+    ($stack:ident [$($tags:tt)*] (oninput = $handler:expr, $($tail:tt)*)) => {
+        html_impl! { $stack [$($tags)*] ((oninput) = $handler, $($tail)*) }
     };
-    ($stack:ident (ondoubleclick = $handler:expr, $($tail:tt)*)) => {
-        html_impl! { $stack ((ondoubleclick) = $handler, $($tail)*) }
+    ($stack:ident [$($tags:tt)*] (onchange = $handler:expr, $($tail:tt)*)) => {
+        html_impl! { $stack [$($tags)*] ((onchange) = $handler, $($tail)*) }
     };
-    ($stack:ident (onkeypress = $handler:expr, $($tail:tt)*)) => {
-        html_impl! { $stack ((onkeypress) = $handler, $($tail)*) }
+    ($stack:ident [$($tags:tt)*] (onsubmit = $handler:expr, $($tail:tt)*)) => {
+        html_impl! { $stack [$($tags)*] ((onsubmit) = $handler, $($tail)*) }
     };
-    ($stack:ident (oninput = $handler:expr, $($tail:tt)*)) => {
-        html_impl! { $stack ((oninput) = $handler, $($tail)*) }
+    // Events: pointer / touch / wheel / scroll
+    ($stack:ident [$($tags:tt)*] (onpointerdown = $handler:expr, $($tail:tt)*)) => {
+        html_impl! { $stack [$($tags)*] ((onpointerdown) = $handler, $($tail)*) }
+    };
+    ($stack:ident [$($tags:tt)*] (onpointerup = $handler:expr, $($tail:tt)*)) => {
+        html_impl! { $stack [$($tags)*] ((onpointerup) = $handler, $($tail)*) }
+    };
+    ($stack:ident [$($tags:tt)*] (onpointermove = $handler:expr, $($tail:tt)*)) => {
+        html_impl! { $stack [$($tags)*] ((onpointermove) = $handler, $($tail)*) }
+    };
+    ($stack:ident [$($tags:tt)*] (ontouchstart = $handler:expr, $($tail:tt)*)) => {
+        html_impl! { $stack [$($tags)*] ((ontouchstart) = $handler, $($tail)*) }
+    };
+    ($stack:ident [$($tags:tt)*] (ontouchmove = $handler:expr, $($tail:tt)*)) => {
+        html_impl! { $stack [$($tags)*] ((ontouchmove) = $handler, $($tail)*) }
+    };
+    ($stack:ident [$($tags:tt)*] (ontouchend = $handler:expr, $($tail:tt)*)) => {
+        html_impl! { $stack [$($tags)*] ((ontouchend) = $handler, $($tail)*) }
+    };
+    ($stack:ident [$($tags:tt)*] (onwheel = $handler:expr, $($tail:tt)*)) => {
+        html_impl! { $stack [$($tags)*] ((onwheel) = $handler, $($tail)*) }
+    };
+    ($stack:ident [$($tags:tt)*] (onscroll = $handler:expr, $($tail:tt)*)) => {
+        html_impl! { $stack [$($tags)*] ((onscroll) = $handler, $($tail)*) }
+    };
+    // PATTERN: (action)=expression, - no open tag to attach it to
+    ($stack:ident [] (($action:ident) = $handler:expr, $($tail:tt)*)) => {
+        compile_error!("event listener with no open tag");
     };
     // PATTERN: (action)=expression,
-    ($stack:ident (($action:ident) = $handler:expr, $($tail:tt)*)) => {
+    ($stack:ident [$($tags:tt)+] (($action:ident) = $handler:expr, $($tail:tt)*)) => {
         // Catch value to a separate variable for clear error messages
         let handler = $handler;
         let listener = $crate::html::$action::Wrapper::from(handler);
         $crate::macros::attach_listener(&mut $stack, Box::new(listener));
-        html_impl! { $stack ($($tail)*) }
+        html_impl! { $stack [$($tags)+] ($($tail)*) }
+    };
+    // PATTERN: attribute=value, - workaround for `type` attribute
+    // because `type` is a keyword in Rust - no open tag to attach it to
+    ($stack:ident [] (type = $val:expr, $($tail:tt)*)) => {
+        compile_error!("`type` attribute with no open tag");
     };
     // PATTERN: attribute=value, - workaround for `type` attribute
     // because `type` is a keyword in Rust
-    ($stack:ident (type = $val:expr, $($tail:tt)*)) => {
+    ($stack:ident [$($tags:tt)+] (type = $val:expr, $($tail:tt)*)) => {
         $crate::macros::add_attribute(&mut $stack, "type", $val);
-        html_impl! { $stack ($($tail)*) }
+        html_impl! { $stack [$($tags)+] ($($tail)*) }
     };
-    ($stack:ident ($attr:ident = $val:expr, $($tail:tt)*)) => {
+    // no open tag to attach the attribute to
+    ($stack:ident [] ($attr:ident = $val:expr, $($tail:tt)*)) => {
+        compile_error!(concat!("`", stringify!($attr), "` attribute with no open tag"));
+    };
+    ($stack:ident [$($tags:tt)+] ($attr:ident = $val:expr, $($tail:tt)*)) => {
         $crate::macros::add_attribute(&mut $stack, stringify!($attr), $val);
-        html_impl! { $stack ($($tail)*) }
+        html_impl! { $stack [$($tags)+] ($($tail)*) }
     };
     // PATTERN: { for expression }
-    ($stack:ident ({ for $eval:expr } $($tail:tt)*)) => {
+    ($stack:ident [$($tags:tt)*] ({ for $eval:expr } $($tail:tt)*)) => {
         let nodes = $eval;
         for node in nodes.map($crate::html::Child::from) {
             $crate::macros::add_child(&mut $stack, node);
         }
-        html_impl! { $stack ($($tail)*) }
+        html_impl! { $stack [$($tags)*] ($($tail)*) }
     };
     // PATTERN: { expression }
-    ($stack:ident ({ $eval:expr } $($tail:tt)*)) => {
+    ($stack:ident [$($tags:tt)*] ({ $eval:expr } $($tail:tt)*)) => {
         let node = $crate::html::Child::from($eval);
         $crate::macros::add_child(&mut $stack, node);
-        html_impl! { $stack ($($tail)*) }
+        html_impl! { $stack [$($tags)*] ($($tail)*) }
+    };
+    // End of opening tag
+    ($stack:ident [$($tags:tt)*] (> $($tail:tt)*)) => {
+        html_impl! { $stack [$($tags)*] ($($tail)*) }
+    };
+    // Explicit empty fragment at the document root: <></>
+    ($stack:ident [] (< > < / > $($tail:tt)*)) => {
+        $crate::macros::add_fragment(&mut $stack, $crate::html::VNode::empty_list(), true);
+        html_impl! { $stack [] ($($tail)*) }
+    };
+    // Explicit empty fragment, nested inside an ancestor: <></>
+    ($stack:ident [$($tags:tt)+] (< > < / > $($tail:tt)*)) => {
+        $crate::macros::add_fragment(&mut $stack, $crate::html::VNode::empty_list(), false);
+        html_impl! { $stack [$($tags)+] ($($tail)*) }
+    };
+    // Self-closing of tag with no open tag to close - redundant
+    ($stack:ident [] (/ > $($tail:tt)*)) => {
+        compile_error!("redundant `/>` with no open tag");
     };
-    // End of openging tag
-    ($stack:ident (> $($tail:tt)*)) => {
-        html_impl! { $stack ($($tail)*) }
+    // Self-closing of tag, back to the document root - a sibling of earlier roots
+    ($stack:ident [$tag:tt] (/ > $($tail:tt)*)) => {
+        $crate::macros::child_to_parent(&mut $stack, None, true);
+        html_impl! { $stack [] ($($tail)*) }
     };
-    // Self-closing of tag
-    ($stack:ident (/ > $($tail:tt)*)) => {
-        $crate::macros::child_to_parent(&mut $stack, None);
-        html_impl! { $stack ($($tail)*) }
+    // Self-closing of tag, still nested inside an ancestor
+    ($stack:ident [$tag:tt $($tags:tt)+] (/ > $($tail:tt)*)) => {
+        $crate::macros::child_to_parent(&mut $stack, None, false);
+        html_impl! { $stack [$($tags)+] ($($tail)*) }
     };
-    // Traditional tag closing
-    ($stack:ident (< / $endtag:ident > $($tail:tt)*)) => {
+    // Traditional tag closing with no open tag to close - redundant
+    ($stack:ident [] (< / $endtag:ident > $($tail:tt)*)) => {
+        compile_error!(concat!("redundant closing tag: </", stringify!($endtag), ">"));
+    };
+    // Traditional tag closing, back to the document root - a sibling of earlier roots
+    ($stack:ident [$tag:tt] (< / $endtag:ident > $($tail:tt)*)) => {
+        let endtag = stringify!($endtag);
+        $crate::macros::child_to_parent(&mut $stack, Some(endtag), true);
+        html_impl! { $stack [] ($($tail)*) }
+    };
+    // Traditional tag closing, still nested inside an ancestor
+    ($stack:ident [$tag:tt $($tags:tt)+] (< / $endtag:ident > $($tail:tt)*)) => {
         let endtag = stringify!($endtag);
-        $crate::macros::child_to_parent(&mut $stack, Some(endtag));
-        html_impl! { $stack ($($tail)*) }
+        $crate::macros::child_to_parent(&mut $stack, Some(endtag), false);
+        html_impl! { $stack [$($tags)+] ($($tail)*) }
     };
-    // "End of paring" rule
-    ($stack:ident ()) => {
+    // "End of parsing" rule
+    ($stack:ident [$($tags:tt)*] ()) => {
         $crate::macros::unpack($stack)
     };
 }
@@ -87,9 +240,41 @@ macro_rules! html_impl {
 // This entrypoint and implementation had separated to prevent infinite recursion.
 #[macro_export]
 macro_rules! html {
+    () => {
+        compile_error!("html! {} requires at least one root element")
+    };
     ($($tail:tt)*) => {
         let mut stack = Vec::new();
-        html_impl! { stack ($($tail)*) }
+        html_impl! { stack [] ($($tail)*) }
+    };
+}
+
+// Parses the body of a `<Name: ...>` component tag until it self-closes, then
+// folds the finished `VComp` back into the surrounding `html_impl!` stack.
+// Component tags are self-closing only, so they never push onto `$tags`.
+#[macro_export]
+macro_rules! html_comp_impl {
+    // PATTERN: key="", - identity used to match this component across
+    // renders; checked ahead of the generic prop arm below so it sets the
+    // diff key instead of being stored as a type-erased `"key"` prop.
+    ($stack:ident [$($tags:tt)*] $comptag:ident $comp:ident (key = $key:expr, $($tail:tt)*)) => {
+        $crate::macros::set_component_key(&mut $comp, $key);
+        html_comp_impl! { $stack [$($tags)*] $comptag $comp ($($tail)*) }
+    };
+    // PATTERN: prop=value,
+    ($stack:ident [$($tags:tt)*] $comptag:ident $comp:ident ($attr:ident = $val:expr, $($tail:tt)*)) => {
+        $crate::macros::set_property(&mut $comp, stringify!($attr), $val);
+        html_comp_impl! { $stack [$($tags)*] $comptag $comp ($($tail)*) }
+    };
+    // Self-closing of the component tag, back at the document root
+    ($stack:ident [] $comptag:ident $comp:ident (/ > $($tail:tt)*)) => {
+        $crate::macros::add_component(&mut $stack, $comp, true);
+        html_impl! { $stack [] ($($tail)*) }
+    };
+    // Self-closing of the component tag, nested inside an ancestor
+    ($stack:ident [$($tags:tt)+] $comptag:ident $comp:ident (/ > $($tail:tt)*)) => {
+        $crate::macros::add_component(&mut $stack, $comp, false);
+        html_impl! { $stack [$($tags)+] ($($tail)*) }
     };
 }
 
@@ -97,10 +282,36 @@ type Stack<MSG> = Vec<VNode<MSG>>;
 
 #[doc(hidden)]
 pub fn unpack<MSG>(mut stack: Stack<MSG>) -> VNode<MSG> {
-    if stack.len() != 1 {
-        panic!("exactly one element have to be in html!");
+    match stack.len() {
+        0 => panic!("at least one element has to be in html!"),
+        1 => stack.pop().unwrap(),
+        // More than one root: wrap the siblings in a fragment so `html!` can
+        // still return a single `VNode`, with no wrapper element in the DOM.
+        _ => VNode::List(stack.into_iter().map(into_root_child).collect()),
+    }
+}
+
+// `add_component` gives a lone root-level `VComp` a `VNode` home by wrapping
+// it as a single-item `VNode::List`, since `Stack` only holds `VNode`s. When
+// folding multiple roots into the outer fragment, that wrapping must be
+// undone here - otherwise the component ends up nested one `VNode::List`
+// deeper than its sibling element roots instead of sitting flat beside them.
+fn into_root_child<MSG>(node: VNode<MSG>) -> Child<MSG> {
+    match node {
+        VNode::List(mut children) if children.len() == 1 => children.pop().unwrap(),
+        other => Child::VNode(other),
+    }
+}
+
+#[doc(hidden)]
+pub fn add_fragment<MSG>(stack: &mut Stack<MSG>, fragment: VNode<MSG>, at_root: bool) {
+    if at_root {
+        // Explicit `<></>` at the document root: keep it as a sibling root,
+        // same as any other completed top-level node.
+        stack.push(fragment);
+    } else {
+        add_child(stack, Child::VNode(fragment));
     }
-    stack.pop().unwrap()
 }
 
 #[doc(hidden)]
@@ -113,9 +324,18 @@ pub fn set_value<MSG, T: ToString>(stack: &mut Stack<MSG>, value: &T) {
 }
 
 #[doc(hidden)]
-pub fn add_attribute<MSG, T: ToString>(stack: &mut Stack<MSG>, name: &'static str, value: T) {
+pub fn set_key<MSG, T: ToString>(stack: &mut Stack<MSG>, key: T) {
     if let Some(node) = stack.last_mut() {
-        node.add_attribute(name, value);
+        node.set_key(key);
+    } else {
+        panic!("no tag to set key: {}", key.to_string());
+    }
+}
+
+#[doc(hidden)]
+pub fn add_attribute<MSG, T: IntoAttribute>(stack: &mut Stack<MSG>, name: &'static str, value: T) {
+    if let Some(node) = stack.last_mut() {
+        node.add_attribute(name, value.into_attribute());
     } else {
         panic!("no tag to set attribute: {}", name);
     }
@@ -139,6 +359,27 @@ pub fn attach_listener<MSG>(stack: &mut Stack<MSG>, listener: Box<Listener<MSG>>
     }
 }
 
+#[doc(hidden)]
+pub fn set_property<MSG, T: 'static>(comp: &mut VComp<MSG>, name: &'static str, value: T) {
+    comp.set_property(name, value);
+}
+
+#[doc(hidden)]
+pub fn set_component_key<MSG, T: ToString>(comp: &mut VComp<MSG>, key: T) {
+    comp.set_key(key);
+}
+
+#[doc(hidden)]
+pub fn add_component<MSG>(stack: &mut Stack<MSG>, comp: VComp<MSG>, at_root: bool) {
+    if at_root {
+        // A lone component at the document root has no element to attach to;
+        // wrap it as a single-item fragment so it can still be a sibling root.
+        stack.push(VNode::List(vec![Child::VComp(comp)]));
+    } else {
+        add_child(stack, Child::VComp(comp));
+    }
+}
+
 #[doc(hidden)]
 pub fn add_child<MSG>(stack: &mut Stack<MSG>, child: Child<MSG>) {
     if let Some(parent) = stack.last_mut() {
@@ -149,21 +390,31 @@ pub fn add_child<MSG>(stack: &mut Stack<MSG>, child: Child<MSG>) {
 }
 
 #[doc(hidden)]
-pub fn child_to_parent<MSG>(stack: &mut Stack<MSG>, endtag: Option<&'static str>) {
+pub fn child_to_parent<MSG>(stack: &mut Stack<MSG>, endtag: Option<&'static str>, at_root: bool) {
     if let Some(node) = stack.pop() {
         let starttag = node.tag();
         if let Some(endtag) = endtag {
+            // A mismatched `<div></span>` can only be caught here: comparing
+            // two arbitrary identifiers for equality isn't possible from
+            // `macro_rules!` alone. The compile-time `$tags` guard in
+            // `html_impl!` rules out every other malformed case before we
+            // get this far, so a `None` stack here should be unreachable;
+            // the `else` branch at the end of this function is kept as a
+            // defensive invariant, not a path we expect to hit.
             if starttag != endtag {
                 panic!("wrong closing tag: <{}> -> </{}>", starttag, endtag);
             }
         }
-        if !stack.is_empty() {
-            stack.last_mut().unwrap().add_child(Child::VNode(node));
-        } else {
-            // Keep the last node in the stack
+        if at_root {
+            // Back at the document root: this is a completed sibling root,
+            // not a child of whatever else is left in the stack - keep it as
+            // its own entry so `unpack` can fold multiple roots into a
+            // fragment.
             stack.push(node);
+        } else {
+            stack.last_mut().unwrap().add_child(Child::VNode(node));
         }
     } else {
         panic!("redundant closing tag: {:?}", endtag);
     }
-}
\ No newline at end of file
+}